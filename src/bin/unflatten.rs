@@ -0,0 +1,189 @@
+use clap::Parser;
+use std::io;
+use std::io::BufRead;
+use serde_json;
+use serde_json::{Map, Value};
+use tabletools::csv_quote::split_fields;
+
+#[derive(Debug, Parser)]
+struct Cli {
+    #[arg(long, short='I', help="between columns of input [default single space]")]
+    input_delimiter: Option<String>,
+    #[arg(long, help="between lines of output [default newline]; input is always read one line per newline")]
+    line_delimiter: Option<String>,
+    #[arg(long, help="in column names, between nested json object keys", default_value=".")]
+    attribute_separator: String,
+    #[arg(long, default_value="", help="cells equal to this are dropped instead of becoming empty strings")]
+    missing: String,
+    #[arg(long, help="parse numbers and booleans instead of leaving every cell as a string")]
+    infer_types: bool,
+    #[arg(long, help="wrap the objects in a json array instead of printing one object per line")]
+    array: bool,
+    #[arg(long, help="parse input quoted per RFC 4180, so a cell can contain the input delimiter (the inverse of unnest's --csv)")]
+    csv: bool,
+    #[arg(long, help="quote character to expect with --csv", default_value="\"")]
+    quote: char,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Cli {
+            input_delimiter: None,
+            line_delimiter: None,
+            attribute_separator: ".".to_string(),
+            missing: "".to_string(),
+            infer_types: false,
+            array: false,
+            csv: false,
+            quote: '"',
+        }
+    }
+}
+
+fn scalar(args: &Cli, cell: &str) -> Value {
+    if !args.infer_types {
+        return Value::String(cell.to_string());
+    }
+    if let Ok(b) = cell.parse::<bool>() {
+        return Value::Bool(b);
+    }
+    if let Ok(n) = cell.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    if let Ok(f) = cell.parse::<f64>() {
+        if let Some(n) = serde_json::Number::from_f64(f) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(cell.to_string())
+}
+
+fn insert_nested(object: &mut Map<String, Value>, path: &[&str], value: Value, column: &str) {
+    if path.len() == 1 {
+        match object.get(path[0]) {
+            Some(Value::Object(_)) => eprintln!("warning: column '{}' conflicts with an earlier nested column of the same prefix, dropping it", column),
+            _ => { object.insert(path[0].to_string(), value); },
+        }
+        return;
+    }
+    let head = path[0].to_string();
+    let sub = object.entry(head).or_insert_with(|| Value::Object(Map::new()));
+    match sub {
+        Value::Object(sub) => insert_nested(sub, &path[1..], value, column),
+        _ => eprintln!("warning: column '{}' conflicts with an earlier scalar column of the same prefix, dropping it", column),
+    }
+}
+
+fn unflatten_row(args: &Cli, columns: &[String], cells: &[String]) -> Value {
+    let mut object = Map::new();
+    for (column, cell) in std::iter::zip(columns, cells) {
+        if cell == &args.missing {
+            continue;
+        }
+        let path: Vec<&str> = column.split(&args.attribute_separator).collect();
+        insert_nested(&mut object, &path, scalar(args, cell), column);
+    }
+    Value::Object(object)
+}
+
+fn main() -> io::Result<()> {
+    let args = Cli::parse();
+    let input_delimiter = match &args.input_delimiter {
+        Some(s) => s,
+        None => " ",
+    };
+    let line_delimiter = match &args.line_delimiter {
+        Some(s) => s,
+        None => "\n",
+    };
+
+    let stdin = io::stdin();
+    let lines = stdin.lock().lines().collect::<io::Result<Vec<String>>>()?;
+    if lines.len() == 0 {
+        return Ok(());
+    }
+
+    let split = |line: &str| -> Vec<String> {
+        if args.csv {
+            split_fields(line, input_delimiter, args.quote)
+        } else {
+            line.split(input_delimiter).map(|s| s.to_string()).collect()
+        }
+    };
+
+    let columns = split(&lines[0]);
+    let rows: Vec<Value> = lines[1..].iter().map(|line| {
+        let cells = split(line);
+        unflatten_row(&args, &columns, &cells)
+    }).collect();
+
+    if args.array {
+        print!("{}", serde_json::to_string(&Value::Array(rows))?);
+        print!("{}", line_delimiter);
+    } else {
+        for row in rows {
+            print!("{}{}", serde_json::to_string(&row)?, line_delimiter);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flat_row() {
+        let columns = vec!["a".to_string(), "b".to_string()];
+        let cells = vec!["alpha".to_string(), "bravo".to_string()];
+        let row = unflatten_row(&Cli::default(), &columns, &cells);
+        assert_eq!(row, json!({"a": "alpha", "b": "bravo"}));
+    }
+
+    #[test]
+    fn nested_row() {
+        let columns = vec!["a".to_string(), "b.c".to_string(), "b.d".to_string()];
+        let cells = vec!["foo".to_string(), "alpha".to_string(), "bravo".to_string()];
+        let row = unflatten_row(&Cli::default(), &columns, &cells);
+        assert_eq!(row, json!({"a": "foo", "b": {"c": "alpha", "d": "bravo"}}));
+    }
+
+    #[test]
+    fn missing_cells_are_dropped() {
+        let columns = vec!["a".to_string(), "b".to_string()];
+        let cells = vec!["alpha".to_string(), "".to_string()];
+        let row = unflatten_row(&Cli::default(), &columns, &cells);
+        assert_eq!(row, json!({"a": "alpha"}));
+    }
+
+    #[test]
+    fn infer_types() {
+        let mut args = Cli::default();
+        args.infer_types = true;
+        let columns = vec!["n".to_string(), "b".to_string(), "s".to_string()];
+        let cells = vec!["123".to_string(), "true".to_string(), "alpha".to_string()];
+        let row = unflatten_row(&args, &columns, &cells);
+        assert_eq!(row, json!({"n": 123, "b": true, "s": "alpha"}));
+    }
+
+    #[test]
+    fn conflicting_nested_column_is_dropped_not_lost_silently() {
+        // "b" is inserted as a scalar first, so "b.c" can't nest under it;
+        // the row should still come back with the scalar, not panic or merge.
+        let columns = vec!["b".to_string(), "b.c".to_string()];
+        let cells = vec!["scalar".to_string(), "nested".to_string()];
+        let row = unflatten_row(&Cli::default(), &columns, &cells);
+        assert_eq!(row, json!({"b": "scalar"}));
+    }
+
+    #[test]
+    fn conflicting_scalar_column_does_not_silently_clobber_nested() {
+        // same conflict, opposite column order: "b.c" builds a nested object
+        // first, so the later scalar "b" must not overwrite it unannounced.
+        let columns = vec!["b.c".to_string(), "b".to_string()];
+        let cells = vec!["nested".to_string(), "scalar".to_string()];
+        let row = unflatten_row(&Cli::default(), &columns, &cells);
+        assert_eq!(row, json!({"b": {"c": "nested"}}));
+    }
+}
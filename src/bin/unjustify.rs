@@ -4,6 +4,8 @@ use std::process;
 use std::io;
 use std::io::BufRead;
 use std::fmt;
+use regex::Regex;
+use tabletools::csv_quote::quote_field;
 
 #[derive(Debug, Clone, ValueEnum)]
 enum SplitWhitespace {
@@ -48,6 +50,12 @@ struct Cli {
     null_end_line: bool,
     #[arg(long, short='H', help="pick columns from first row only")]
     header: bool,
+    #[arg(long, help="quote fields per RFC 4180 instead of joining them raw")]
+    csv: bool,
+    #[arg(long, help="quote character to use with --csv", default_value="\"")]
+    quote: char,
+    #[arg(long, help="match this pattern against each line; matched characters count as delimiters, combined with --delimiters and --whitespace")]
+    regex: Option<Regex>,
 }
 
 impl Default for Cli {
@@ -64,6 +72,9 @@ impl Default for Cli {
             line_delimiter: None,
             record_separator: false,
             null_end_line: false,
+            csv: false,
+            quote: '"',
+            regex: None,
         }
     }
 }
@@ -117,24 +128,47 @@ fn some_whitespace(c: Option<char>) -> bool {
     }
 }
 
-fn is_delimiter(args: &Cli, before: Option<char>, c: char, after: Option<char>) -> bool {
+fn is_delimiter(args: &Cli, before: Option<char>, c: char, after: Option<char>, regex_hit: bool) -> bool {
     let matches_whitespace = match args.whitespace {
         SplitWhitespace::Any => c.is_whitespace(),
         SplitWhitespace::Ignore => false,
         SplitWhitespace::Double => c.is_whitespace() && (some_whitespace(before) || some_whitespace(after))
     };
     let matches_delimiters = args.delimiters.contains(c);
-    matches_whitespace || matches_delimiters
+    matches_whitespace || matches_delimiters || regex_hit
+}
+
+// marks the char indices of `string` covered by any `--regex` match, so
+// those positions feed into `update_spaces` alongside --delimiters/--whitespace
+fn regex_hits(regex: &Option<Regex>, string: &str) -> Vec<bool> {
+    let mut hits = vec![false; string.chars().count()];
+    let regex = match regex {
+        Some(r) => r,
+        None => return hits,
+    };
+    let char_at_byte: HashMap<usize, usize> = string.char_indices().enumerate().map(|(i, (b, _))| (b, i)).collect();
+    for m in regex.find_iter(string) {
+        let start = *char_at_byte.get(&m.start()).unwrap_or(&hits.len());
+        let end = char_at_byte.get(&m.end()).copied().unwrap_or(hits.len());
+        for i in start..end {
+            if let Some(hit) = hits.get_mut(i) {
+                *hit = true;
+            }
+        }
+    }
+    hits
 }
 
 fn update_spaces(args: &Cli, mut spaces: Vec<bool>, string: &String) -> Vec<bool> {
     let chars: Vec<char> = string.chars().collect();
+    let regex_hits = regex_hits(&args.regex, string);
     for (i, c) in chars.iter().enumerate() {
         let before = if i > 0 { Some(chars[i-1]) } else { None };
         let after = if i + 1 < chars.len() { Some(chars[i+1])} else { None };
+        let hit = regex_hits.get(i).copied().unwrap_or(false);
         match spaces.get_mut(i) {
-            Some(space) => *space = *space && is_delimiter(args, before, *c, after),
-            None => spaces.push(is_delimiter(args, before, *c, after)),
+            Some(space) => *space = *space && is_delimiter(args, before, *c, after, hit),
+            None => spaces.push(is_delimiter(args, before, *c, after, hit)),
         }
     }
     spaces
@@ -172,6 +206,27 @@ fn split_line(columns: &[(usize, usize)], line: &str) -> Vec<String> {
     out
 }
 
+// a 1-based selector like `3`, `2-5`, `-3` (first three), or `4-` (from the
+// fourth on). Returns None when `token` isn't one of these numeric forms, so
+// the caller can fall back to matching it against a column name.
+fn numeric_selector(token: &str, num_columns: usize) -> Option<Vec<usize>> {
+    if let Ok(n) = token.parse::<usize>() {
+        return Some(vec![n]);
+    }
+    if let Some(rest) = token.strip_prefix('-') {
+        return rest.parse::<usize>().ok().map(|n| (1..=n).collect());
+    }
+    if let Some(rest) = token.strip_suffix('-') {
+        return rest.parse::<usize>().ok().map(|n| (n..=num_columns).collect());
+    }
+    if let Some((a, b)) = token.split_once('-') {
+        if let (Ok(a), Ok(b)) = (a.parse::<usize>(), b.parse::<usize>()) {
+            return Some((a..=b).collect());
+        }
+    }
+    None
+}
+
 fn output_columns(columns: &[(usize, usize)], header: &str, desired: &[String], insensitive: bool) -> Vec<(usize, usize)>{
     if desired.len() == 0 {
         return columns.to_vec();
@@ -189,6 +244,14 @@ fn output_columns(columns: &[(usize, usize)], header: &str, desired: &[String],
 
     let mut ret = Vec::new();
     for head in desired {
+        if let Some(indices) = numeric_selector(head, columns.len()) {
+            for i in indices {
+                if i >= 1 && i <= columns.len() {
+                    ret.push(columns[i - 1]);
+                }
+            }
+            continue;
+        }
         let o_range = if insensitive {
             let h = head.to_lowercase();
             mapping.get(&h)
@@ -229,9 +292,14 @@ fn main() -> io::Result<()> {
     let columns = columns(&spaces);
     let output_columns = output_columns(&columns, &lines[0], args.output_columns.as_ref(), args.insensitive);
 
+    let output_delimiter = args.computed_output_delimiter();
+    let line_delimiter = args.computed_line_delimiter();
     for string in lines {
-        let outln = split_line(&output_columns, &string);
-        print!("{}{}", &outln.join(&args.computed_output_delimiter()), args.computed_line_delimiter());
+        let mut outln = split_line(&output_columns, &string);
+        if args.csv {
+            outln = outln.iter().map(|f| quote_field(f, &output_delimiter, &line_delimiter, args.quote)).collect();
+        }
+        print!("{}{}", &outln.join(&output_delimiter), &line_delimiter);
     }
 
     Ok(())
@@ -328,4 +396,59 @@ pub mod tests {
         let runs = columns(&vec![true, false, false, true, false, false, true, false, false, false, false]);
         assert_eq!(runs, vec![(1, 3), (4, 6), (7, 11)]);
     }
+
+    #[test]
+    fn numeric_selector_index() {
+        assert_eq!(numeric_selector("3", 5), Some(vec![3]));
+    }
+
+    #[test]
+    fn numeric_selector_range() {
+        assert_eq!(numeric_selector("2-5", 5), Some(vec![2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn numeric_selector_first_n() {
+        assert_eq!(numeric_selector("-3", 5), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn numeric_selector_from_n() {
+        assert_eq!(numeric_selector("4-", 5), Some(vec![4, 5]));
+    }
+
+    #[test]
+    fn numeric_selector_rejects_names() {
+        assert_eq!(numeric_selector("name", 5), None);
+    }
+
+    #[test]
+    fn output_columns_numeric_and_name_mixed() {
+        let columns_ = vec![(0, 4), (5, 8), (10, 14), (15, 20)];
+        let header = "name age  city state";
+        let desired = vec!["name".to_string(), "3-4".to_string()];
+        let ret = output_columns(&columns_, header, &desired, false);
+        assert_eq!(ret, vec![(0, 4), (10, 14), (15, 20)]);
+    }
+
+    #[test]
+    fn update_spaces_regex() {
+        let mut args: Cli = Default::default();
+        args.whitespace = SplitWhitespace::Ignore;
+        args.regex = Some(Regex::new(r" \| ").unwrap());
+        let mut line = String::new();
+        line.push_str("a | bb | ccc");
+        let spaces = update_spaces(&args, Vec::new(), &line);
+        assert_eq!(spaces, vec![false, true, true, true, false, false, true, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn update_spaces_regex_combines_with_whitespace() {
+        let mut args: Cli = Default::default();
+        args.regex = Some(Regex::new(r"\|").unwrap());
+        let mut line = String::new();
+        line.push_str("a | bb");
+        let spaces = update_spaces(&args, Vec::new(), &line);
+        assert_eq!(spaces, vec![false, true, true, true, false, false]);
+    }
 }
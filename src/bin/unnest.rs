@@ -1,8 +1,27 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::io;
+use std::io::{BufRead, Read};
 use std::collections::HashMap;
+use std::process;
 use serde_json;
 use serde_json::{Map, Value};
+use tabletools::csv_quote::quote_field;
+
+#[derive(Debug, Clone, ValueEnum)]
+enum InputFormat {
+    Json,
+    Toml,
+}
+
+impl std::fmt::Display for InputFormat {
+    fn fmt(&self, out: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use InputFormat::*;
+        match self {
+            Json => write!(out, "{}", "json"),
+            Toml => write!(out, "{}", "toml"),
+        }
+    }
+}
 
 #[derive(Debug, Parser)]
 struct Cli {
@@ -14,6 +33,16 @@ struct Cli {
     attribute_separator: String,
     #[arg(long, default_value="", help="output representation of missing values")]
     missing: String,
+    #[arg(long="input-format", value_enum, default_value_t=InputFormat::Json, help="format of stdin")]
+    input_format: InputFormat,
+    #[arg(long, help="read one json value per line instead of a single document")]
+    jsonl: bool,
+    #[arg(long, help="with --jsonl, make a first pass over the input to collect every column before printing the header")]
+    buffer_header: bool,
+    #[arg(long, help="quote fields per RFC 4180 instead of joining them raw")]
+    csv: bool,
+    #[arg(long, help="quote character to use with --csv", default_value="\"")]
+    quote: char,
 }
 
 impl Default for Cli {
@@ -23,6 +52,11 @@ impl Default for Cli {
             line_delimiter: None,
             attribute_separator: ".".to_string(),
             missing: "".to_string(),
+            input_format: InputFormat::Json,
+            jsonl: false,
+            buffer_header: false,
+            csv: false,
+            quote: '"',
         }
     }
 }
@@ -92,6 +126,150 @@ fn recurse_value(args: &Cli, columns: &mut Columns, path: &str, value: Value) ->
     }
 }
 
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => match serde_json::Number::from_f64(f) {
+            Some(n) => Value::Number(n),
+            None => Value::Null,
+        },
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(arr) => Value::Array(arr.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => {
+            let mut map = Map::new();
+            for (k, v) in table {
+                map.insert(k, toml_to_json(v));
+            }
+            Value::Object(map)
+        },
+    }
+}
+
+// TOML documents are always wrapped in a top-level table, so a single
+// top-level `[[name]]` array of tables parses as a table with one key whose
+// value is that array. Unwrap it to the bare array so it flattens into
+// unprefixed rows, matching what a top-level JSON array already does.
+// A dotted `[[a.b]]` header parses as nested tables and is not unwrapped.
+fn unwrap_outer_array_of_tables(value: toml::Value) -> toml::Value {
+    if let toml::Value::Table(table) = &value {
+        if table.len() == 1 {
+            let only = table.values().next().unwrap();
+            if let toml::Value::Array(arr) = only {
+                if !arr.is_empty() && arr.iter().all(|v| matches!(v, toml::Value::Table(_))) {
+                    return only.clone();
+                }
+            }
+        }
+    }
+    value
+}
+
+fn quoted(args: &Cli, field: &str, output_delimiter: &str, line_delimiter: &str) -> String {
+    if args.csv {
+        quote_field(field, output_delimiter, line_delimiter, args.quote)
+    } else {
+        field.to_string()
+    }
+}
+
+fn print_header(args: &Cli, columns: &Columns, output_delimiter: &str, line_delimiter: &str) {
+    let out: Vec<String> = columns.iter().map(|c| quoted(args, c, output_delimiter, line_delimiter)).collect();
+    print!("{}{}", out.join(output_delimiter), line_delimiter);
+}
+
+fn print_rows(args: &Cli, columns: &Columns, rows: &[Row], output_delimiter: &str, line_delimiter: &str) {
+    for r in rows.iter() {
+        let mut out = Vec::new();
+        for c in columns.iter() {
+            let field = match r.get(c) {
+                Some(s) => s.clone(),
+                None => args.missing.clone(),
+            };
+            out.push(quoted(args, &field, output_delimiter, line_delimiter));
+        }
+        print!("{}{}", out.join(output_delimiter), line_delimiter);
+    }
+}
+
+fn read_whole_document(args: &Cli) -> io::Result<Value> {
+    match args.input_format {
+        InputFormat::Json => serde_json::from_reader(io::stdin()).map_err(io::Error::from),
+        InputFormat::Toml => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            match text.parse::<toml::Value>() {
+                Ok(v) => Ok(toml_to_json(unwrap_outer_array_of_tables(v))),
+                Err(e) => {
+                    eprintln!("error parsing toml: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+fn parse_jsonl_line(line: &str) -> Value {
+    match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("error parsing json line: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// one json value per input line; --buffer-header makes a first pass to collect
+// the full union of columns before printing anything, while the default
+// streaming behavior fixes the header from the first record and warns about
+// any column discovered later.
+fn run_jsonl(args: &Cli, output_delimiter: &str, line_delimiter: &str) -> io::Result<()> {
+    let stdin = io::stdin();
+
+    if args.buffer_header {
+        // needs every record in hand before the header can be fixed, so this
+        // pass necessarily buffers the whole input
+        let lines: Vec<String> = stdin.lock().lines().collect::<io::Result<Vec<String>>>()?
+            .into_iter().filter(|l| !l.trim().is_empty()).collect();
+        let mut columns: Columns = Vec::new();
+        let records: Vec<Value> = lines.iter().map(|l| parse_jsonl_line(l)).collect();
+        for record in &records {
+            recurse_value(args, &mut columns, "", record.clone());
+        }
+        print_header(args, &columns, output_delimiter, line_delimiter);
+        for record in records {
+            let mut local_columns = columns.clone();
+            let rows = recurse_value(args, &mut local_columns, "", record);
+            print_rows(args, &columns, &rows, output_delimiter, line_delimiter);
+        }
+    } else {
+        // stream: never hold more than one record in memory at a time
+        let mut columns: Columns = Vec::new();
+        let mut header_printed = false;
+        for line in stdin.lock().lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = parse_jsonl_line(&line);
+            let mut record_columns = if header_printed { columns.clone() } else { Vec::new() };
+            let rows = recurse_value(args, &mut record_columns, "", record);
+            if !header_printed {
+                columns = record_columns;
+                print_header(args, &columns, output_delimiter, line_delimiter);
+                header_printed = true;
+            } else if record_columns.len() > columns.len() {
+                for c in &record_columns[columns.len()..] {
+                    eprintln!("warning: column '{}' found after header was already printed, dropping it", c);
+                }
+            }
+            print_rows(args, &columns, &rows, output_delimiter, line_delimiter);
+        }
+    }
+    Ok(())
+}
+
 fn main() -> io::Result<()> {
     let args = Cli::parse();
     let output_delimiter = match &args.output_delimiter {
@@ -103,24 +281,19 @@ fn main() -> io::Result<()> {
         None => "\n",
     };
 
-    let json: Value = serde_json::from_reader(io::stdin())?;
+    if args.jsonl {
+        return run_jsonl(&args, output_delimiter, line_delimiter);
+    }
+
+    let json = read_whole_document(&args)?;
 
     // recurse into json, building columns & rows as we go
     let mut columns: Columns = Vec::new();
     let rows = recurse_value(&args, &mut columns, "", json);
 
     // output
-    print!("{}{}", columns.join(output_delimiter), line_delimiter);
-    for r in rows.iter() {
-        let mut out = Vec::new();
-        for c in columns.iter() {
-            out.push(match r.get(c) {
-                Some(s) => s.clone(),
-                None => args.missing.clone(),
-            });
-        }
-        print!("{}{}", out.join(output_delimiter), line_delimiter);
-    }
+    print_header(&args, &columns, output_delimiter, line_delimiter);
+    print_rows(&args, &columns, &rows, output_delimiter, line_delimiter);
     Ok(())
 }
 
@@ -262,5 +435,18 @@ pub mod tests {
         assert_columns_and_rows(input, "a b c", &vec!["a:alpha b: c:", "a: b:bravo c:charlie"]);
     }
 
+    #[test]
+    fn unwrap_top_level_array_of_tables() {
+        let toml: toml::Value = "[[rows]]\na = \"alpha\"\n[[rows]]\na = \"bravo\"\n".parse().unwrap();
+        let unwrapped = unwrap_outer_array_of_tables(toml);
+        let json = toml_to_json(unwrapped);
+        assert_eq!(json, json!([{"a": "alpha"}, {"a": "bravo"}]));
+    }
 
+    #[test]
+    fn leave_other_tables_unwrapped() {
+        let toml: toml::Value = "a = \"alpha\"\nb = \"bravo\"\n".parse().unwrap();
+        let unwrapped = unwrap_outer_array_of_tables(toml.clone());
+        assert_eq!(unwrapped, toml);
+    }
 }
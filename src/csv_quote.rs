@@ -0,0 +1,117 @@
+// RFC 4180 quoting, shared by unnest's and unjustify's output loops.
+
+fn needs_quoting(field: &str, output_delimiter: &str, line_delimiter: &str, quote: char) -> bool {
+    field.contains(output_delimiter)
+        || field.contains(line_delimiter)
+        || field.contains(quote)
+        || field.starts_with(' ')
+        || field.ends_with(' ')
+}
+
+/// Quote `field` per RFC 4180 if it contains the output delimiter, the line
+/// delimiter, a leading/trailing space, or the quote character itself.
+/// Embedded quote characters are doubled. Fields that need no quoting are
+/// returned unchanged.
+pub fn quote_field(field: &str, output_delimiter: &str, line_delimiter: &str, quote: char) -> String {
+    if !needs_quoting(field, output_delimiter, line_delimiter, quote) {
+        return field.to_string();
+    }
+    let mut doubled = quote.to_string();
+    doubled.push(quote);
+    let escaped = field.replace(quote, &doubled);
+    format!("{}{}{}", quote, escaped, quote)
+}
+
+/// Inverse of `quote_field`: split a single line on `delimiter`, honoring RFC
+/// 4180 quoting so a quoted field may itself contain the delimiter or the
+/// quote character (doubled). A quote only opens a quoted field when it's the
+/// first character of that field, matching what `quote_field` produces. Does
+/// not handle a quoted field spanning multiple physical lines.
+pub fn split_fields(line: &str, delimiter: &str, quote: char) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let delim: Vec<char> = delimiter.chars().collect();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < chars.len() {
+        if in_quotes {
+            if chars[i] == quote {
+                if chars.get(i + 1) == Some(&quote) {
+                    field.push(quote);
+                    i += 2;
+                } else {
+                    in_quotes = false;
+                    i += 1;
+                }
+            } else {
+                field.push(chars[i]);
+                i += 1;
+            }
+        } else if chars[i] == quote && field.is_empty() {
+            in_quotes = true;
+            i += 1;
+        } else if !delim.is_empty() && chars[i..].starts_with(delim.as_slice()) {
+            fields.push(std::mem::take(&mut field));
+            i += delim.len();
+        } else {
+            field.push(chars[i]);
+            i += 1;
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_field_unchanged() {
+        assert_eq!(quote_field("alpha", ",", "\n", '"'), "alpha");
+    }
+
+    #[test]
+    fn field_with_delimiter_is_quoted() {
+        assert_eq!(quote_field("a, b", ",", "\n", '"'), "\"a, b\"");
+    }
+
+    #[test]
+    fn field_with_quote_is_escaped() {
+        assert_eq!(quote_field("say \"hi\"", ",", "\n", '"'), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn field_with_leading_or_trailing_space_is_quoted() {
+        assert_eq!(quote_field(" alpha", ",", "\n", '"'), "\" alpha\"");
+        assert_eq!(quote_field("alpha ", ",", "\n", '"'), "\"alpha \"");
+    }
+
+    #[test]
+    fn field_with_line_delimiter_is_quoted() {
+        assert_eq!(quote_field("a\nb", ",", "\n", '"'), "\"a\nb\"");
+    }
+
+    #[test]
+    fn split_fields_plain() {
+        assert_eq!(split_fields("a,b,c", ",", '"'), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn split_fields_quoted_delimiter() {
+        assert_eq!(split_fields("\"a, b\",c", ",", '"'), vec!["a, b", "c"]);
+    }
+
+    #[test]
+    fn split_fields_quoted_doubled_quote() {
+        assert_eq!(split_fields("\"say \"\"hi\"\"\",c", ",", '"'), vec!["say \"hi\"", "c"]);
+    }
+
+    #[test]
+    fn split_fields_round_trips_with_quote_field() {
+        let fields = vec!["a, b".to_string(), "say \"hi\"".to_string(), "plain".to_string()];
+        let line: Vec<String> = fields.iter().map(|f| quote_field(f, ",", "\n", '"')).collect();
+        assert_eq!(split_fields(&line.join(","), ",", '"'), fields);
+    }
+}
@@ -0,0 +1 @@
+pub mod csv_quote;